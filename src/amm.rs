@@ -0,0 +1,124 @@
+use super::arbitrage::calculate_weighted_average_price;
+use super::models::Direction;
+
+use rust_decimal::Decimal;
+
+/// Constant-Product Pool
+/// Synthetic venue backed by an on-chain AMM reserve pair (`reserve_base` of the base
+/// asset, `reserve_quote` of the quote asset) with a proportional swap fee, so DEX
+/// liquidity can be walked by the same code path as a Binance order book.
+#[derive(Debug, Clone, Copy)]
+pub struct ConstantProductPool {
+    pub reserve_base: Decimal,
+    pub reserve_quote: Decimal,
+    pub fee: Decimal, // e.g. dec!(0.003) for 0.3%
+}
+
+impl ConstantProductPool {
+    pub fn new(reserve_base: Decimal, reserve_quote: Decimal, fee: Decimal) -> Self {
+        Self { reserve_base, reserve_quote, fee }
+    }
+
+    /// Swap Out
+    /// Constant-product output for spending `dx` of the base asset:
+    /// `dy = y - k / (x + dx * (1 - fee))`, `k = x * y`
+    fn swap_out(&self, dx: Decimal) -> Option<Decimal> {
+        if dx <= Decimal::ZERO {
+            return None;
+        }
+
+        let k = self.reserve_base * self.reserve_quote;
+        let dx_after_fee = dx * (Decimal::ONE - self.fee);
+        let new_reserve_base = self.reserve_base + dx_after_fee;
+        if new_reserve_base.is_zero() {
+            return None;
+        }
+
+        let dy = self.reserve_quote - (k / new_reserve_base);
+        if dy <= Decimal::ZERO || dy >= self.reserve_quote {
+            return None;
+        }
+
+        Some(dy)
+    }
+
+    /// Reversed
+    /// Flips base/quote so the pool's rate in the other direction walks the same curve
+    pub fn reversed(&self) -> Self {
+        Self { reserve_base: self.reserve_quote, reserve_quote: self.reserve_base, fee: self.fee }
+    }
+
+    /// Spot Price
+    /// Instantaneous (zero-size), fee-ignoring marginal price of the pool - the AMM-venue
+    /// equivalent of an order book's top-of-book price. `calculate_arbitrage` only uses it to
+    /// reject a leg on a drained pool; a pool leg's execution price is not compared against it
+    /// or `MARKET_PRICE_TOLERANCE`. `None` for a drained/malformed pool
+    /// (`reserve_base == 0`) rather than panicking on the divide.
+    pub fn spot_price(&self) -> Option<Decimal> {
+        if self.reserve_base.is_zero() {
+            return None;
+        }
+        Some(self.reserve_quote / self.reserve_base)
+    }
+
+    /// Weighted Average Price
+    /// Derives the effective execution curve for a budget of the leg's input asset,
+    /// returning the same `(weighted_price, total_cost, total_quantity)` shape
+    /// `calculate_weighted_average_price` returns for an order book, so a pool edge slots
+    /// into `calculate_arbitrage` without the caller knowing the difference.
+    pub fn weighted_average_price(&self, budget: Decimal, direction: &Direction) -> Option<(Decimal, Decimal, Decimal)> {
+        let pool = match direction {
+            Direction::Forward => *self,
+            Direction::Reverse => self.reversed(),
+        };
+
+        let total_quantity = pool.swap_out(budget)?;
+        let total_cost = budget;
+
+        let weighted_price = match direction {
+            Direction::Reverse => total_cost / total_quantity,
+            Direction::Forward => total_quantity / total_cost,
+        };
+
+        Some((weighted_price, total_cost, total_quantity))
+    }
+}
+
+/// Venue
+/// A cycle leg's liquidity source - a centralized order book, or a constant-product AMM
+/// pool - so `validate_arbitrage_cycle` can mix both within a single cycle.
+#[derive(Debug, Clone)]
+pub enum Venue {
+    OrderBook(Vec<(Decimal, Decimal)>),
+    Pool(ConstantProductPool),
+}
+
+impl Venue {
+    /// Dispatches to the order-book walk or the AMM curve depending on which venue backs
+    /// this leg, both ultimately returning the same execution tuple.
+    pub fn weighted_average_price(&self, budget: Decimal, direction: &Direction) -> Option<(Decimal, Decimal, Decimal)> {
+        match self {
+            Venue::OrderBook(book) => calculate_weighted_average_price(book, budget, direction),
+            Venue::Pool(pool) => pool.weighted_average_price(budget, direction),
+        }
+    }
+
+    /// Top Of Book Price
+    /// The order book's own best level, or the pool's spot price in the direction this leg
+    /// trades. `calculate_arbitrage` rejects a leg when this is `None`, and sanity-checks an
+    /// order-book leg's weighted execution price against it; a pool leg's execution price is
+    /// not checked against it. `None` for an empty book, a zero-priced top level or a drained
+    /// pool, since none is a usable reference to divide by.
+    pub fn top_of_book_price(&self, direction: &Direction) -> Option<Decimal> {
+        match self {
+            Venue::OrderBook(book) => book.first().map(|&(price, _)| price).filter(|price| !price.is_zero()),
+            Venue::Pool(pool) => {
+                let pool = match direction {
+                    Direction::Forward => *pool,
+                    Direction::Reverse => pool.reversed(),
+                };
+                pool.spot_price()
+            }
+        }
+    }
+}
@@ -1,4 +1,5 @@
-use super::constants::{ASSET_HOLDINGS, USD_BUDGET, MAX_SYMBOLS_WATCH, MIN_ARB_THRESH, UPDATE_SYMBOLS_SECONDS};
+use super::amm::{ConstantProductPool, Venue};
+use super::constants::{ASSET_HOLDINGS, USD_BUDGET, MARKET_PRICE_TOLERANCE, MAX_BOOK_AGE_SECONDS, MAX_SYMBOLS_WATCH, ROUTER_BUDGET_STEPS, SURFACE_RATE_WEIGHT_BOUND, UPDATE_SYMBOLS_SECONDS, arb_thresh_for_asset};
 use super::bellmanford::Edge;
 use crate::exchanges::binance::Binance;
 use super::helpers;
@@ -7,20 +8,53 @@ use super::traits::{ApiCalls, BellmanFordEx, ExchangeData};
 
 use csv::Writer;
 use futures::future::join_all;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use std::fmt;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use std::fs::OpenOptions;
-use std::collections::HashSet;
+
+/// Cycle Rejection
+/// Why `validate_arbitrage_cycle` discarded an otherwise Bellman-Ford-qualifying cycle,
+/// surfaced so `route_budget_across_cycles` (run from `best_symbols_thread`'s loop) and
+/// `run_backtest` log the reason instead of silently dropping it.
+#[derive(Debug)]
+pub enum CycleRejection {
+    EmptyCycle,
+    UnrecognisedStart(String),
+    OrderbookFetchFailed(SmartError),
+    InsufficientDepth,
+    StaleBook { symbol: String, age_secs: u64 },
+    OutsideMarketPrice { symbol: String, deviation_pct: Decimal },
+}
+
+impl fmt::Display for CycleRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CycleRejection::EmptyCycle => write!(f, "cycle was empty"),
+            CycleRejection::UnrecognisedStart(asset) => write!(f, "{} not recognised as meaningful starting point", asset),
+            CycleRejection::OrderbookFetchFailed(e) => write!(f, "orderbook fetch failed: {:?}", e),
+            CycleRejection::InsufficientDepth => write!(f, "insufficient depth to fill budget"),
+            CycleRejection::StaleBook { symbol, age_secs } => write!(f, "{} book is {}s old, exceeds max age", symbol, age_secs),
+            CycleRejection::OutsideMarketPrice { symbol, deviation_pct } => write!(f, "{} execution price deviates {}% from top of book", symbol, deviation_pct),
+        }
+    }
+}
 
 /// Calculate Weighted Average Price
-/// Calculates the depth of the orderbook to get a real rate
-fn calculate_weighted_average_price(
-    orderbook: &Vec<(f64, f64)>,
-    budget: f64,
+/// Calculates the depth of the orderbook to get a real rate. Prices, quantities and the
+/// running budget are `Decimal` so a five-leg cycle can't drift across `MIN_ARB_THRESH`
+/// the way repeated `f64` products and divisions can. `pub(crate)` so `amm::Venue` can walk
+/// a centralized book through the same curve it uses for a pool.
+pub(crate) fn calculate_weighted_average_price(
+    orderbook: &Vec<(Decimal, Decimal)>,
+    budget: Decimal,
     direction: &Direction,
-) -> Option<(f64, f64, f64)> {
-    let mut total_cost = 0.0;
-    let mut total_quantity = 0.0;
+) -> Option<(Decimal, Decimal, Decimal)> {
+    let mut total_cost = Decimal::ZERO;
+    let mut total_quantity = Decimal::ZERO;
 
     for &(price, quantity) in orderbook.iter() {
 
@@ -60,7 +94,7 @@ fn calculate_weighted_average_price(
         }
     }
 
-    if total_quantity == 0.0 {
+    if total_quantity.is_zero() {
         return None;
     }
 
@@ -69,23 +103,31 @@ fn calculate_weighted_average_price(
         Direction::Reverse => total_cost / total_quantity,
         Direction::Forward => total_quantity / total_cost,
     };
-  
+
     Some((weighted_average_price, total_cost, total_quantity))
 }
 
 /// Calculate Arbitrage
-/// Calculates arbitrage given relevant inputs and orderbooks
+/// Calculates arbitrage given relevant inputs and venues. `real_rate` and the running
+/// `amount_in` stay `Decimal` end to end; the only conversion back to `f64` is each leg's
+/// recorded input quantity. For a centralized order book leg it goes through
+/// `helpers::validate_quantity`, which validates against exchange lot-size rules expressed
+/// in the exchange's own `f64` API types; a pool leg's amount is converted directly, since an
+/// AMM pool has no exchange-imposed lot size. Each order-book leg's implied execution price is
+/// sanity-checked against its book's top level so a crossed or empty book can't produce a
+/// phantom rate; a pool leg is only rejected when the pool is drained, and its execution
+/// price is not checked against its spot price.
 fn calculate_arbitrage<T>(
-    orderbooks: &Vec<Vec<(f64, f64)>>,
+    venues: &Vec<Venue>,
     symbols: &Vec<String>,
     directions: &Vec<Direction>,
-    budget: f64,
+    budget: Decimal,
     exchange: &T,
-) -> Option<(f64, Vec<f64>)> 
+) -> Result<(Decimal, Vec<f64>), CycleRejection>
 where T: BellmanFordEx + ExchangeData + ApiCalls {
 
     // Initialize
-    let mut real_rate = 1.0;
+    let mut real_rate = Decimal::ONE;
     let mut quantities_input = vec![];
     let mut amount_in = budget;
 
@@ -93,82 +135,133 @@ where T: BellmanFordEx + ExchangeData + ApiCalls {
     for i in 0..symbols.len() {
         let symbol = &symbols[i];
         let direction = &directions[i];
-        let orderbook = &orderbooks[i];
-
-        // Guard: Validate quantity
-        let symbol_info = exchange.symbols().get(symbol.as_str())?;
-        let price = exchange.prices().get(symbol.as_str())?;
-        let quantity = match helpers::validate_quantity(symbol_info, amount_in, *price) {
-            Ok(quantity) => quantity,
-            Err(_e) => {
-                // eprintln!("Failed to validate quantity: {:?}", _e);
-                return None;
+        let venue = &venues[i];
+
+        // Guard: Ensure depth
+        let top_of_book_price = venue.top_of_book_price(direction).ok_or(CycleRejection::InsufficientDepth)?;
+
+        // Guard: Validate quantity (exchange API boundary - amount_in crosses to f64 here).
+        // Only a centralized order-book leg is bound by exchange lot-size rules.
+        let quantity = match venue {
+            Venue::OrderBook(_) => {
+                let symbol_info = exchange.symbols().get(symbol.as_str()).ok_or(CycleRejection::InsufficientDepth)?;
+                let price = exchange.prices().get(symbol.as_str()).ok_or(CycleRejection::InsufficientDepth)?;
+                let amount_in_f64 = amount_in.to_f64().ok_or(CycleRejection::InsufficientDepth)?;
+                match helpers::validate_quantity(symbol_info, amount_in_f64, *price) {
+                    Ok(quantity) => quantity,
+                    Err(_e) => {
+                        // eprintln!("Failed to validate quantity: {:?}", _e);
+                        return Err(CycleRejection::InsufficientDepth);
+                    }
+                }
             }
+            Venue::Pool(_) => amount_in.to_f64().ok_or(CycleRejection::InsufficientDepth)?,
         };
 
         // Add quantity
         quantities_input.push(quantity);
 
         // Calculate Average Price and quantity out - first pass
-        let trade_res: Option<(f64, f64, f64)> = calculate_weighted_average_price(orderbook, amount_in, &direction);
+        let trade_res: Option<(Decimal, Decimal, Decimal)> = venue.weighted_average_price(amount_in, direction);
         let (weighted_price, total_qty) = match trade_res {
             Some((wp, _, qty)) => (wp, qty),
             None => {
                 // eprintln!("Error calculating weighted price...");
-                return None;
+                return Err(CycleRejection::InsufficientDepth);
             }
         };
 
+        // Guard: "outside market price" - reject an order-book leg whose implied execution
+        // price has drifted too far from its own top of book (the tell for a crossed or
+        // stale side). Doesn't apply to a pool leg: its weighted price comes straight off the
+        // constant-product curve rather than external quoted data, so walking a non-trivial
+        // fraction of its reserves is expected to move the price well past
+        // `MARKET_PRICE_TOLERANCE` on a perfectly healthy pool.
+        if let Venue::OrderBook(_) = venue {
+            let deviation = ((weighted_price - top_of_book_price) / top_of_book_price).abs();
+            if deviation > MARKET_PRICE_TOLERANCE {
+                return Err(CycleRejection::OutsideMarketPrice { symbol: symbol.clone(), deviation_pct: deviation * Decimal::ONE_HUNDRED });
+            }
+        }
+
         // Update budget amount in
         amount_in = total_qty;
 
         // Calculate Real Rate
         match direction {
             Direction::Forward => real_rate *= weighted_price,
-            Direction::Reverse => real_rate *= 1.0 / weighted_price,
+            Direction::Reverse => real_rate *= Decimal::ONE / weighted_price,
         }
     }
 
     // Return results
-    Some((real_rate, quantities_input))
+    Ok((real_rate, quantities_input))
 }
 
 
-/// Validate Arbitrage Cycle
-/// Validates arbitrage cycle has enough depth
-pub async fn validate_arbitrage_cycle<T: BellmanFordEx>(cycle: &Vec<Edge>, exchange: &T) -> Option<(f64, Vec<f64>, Vec<String>)> 
+/// Starting Budget
+/// Resolves the USD-equivalent budget available for a cycle starting from `from`. The
+/// reference price crosses the exchange API boundary as `f64` and is converted to
+/// `Decimal` exactly once, here.
+fn starting_budget<T: ExchangeData>(from: &str, exchange: &T) -> Option<Decimal> {
+    let price_in_usdt = |symbol: &str| -> Option<Decimal> {
+        Decimal::from_f64(*exchange.prices().get(symbol)?)
+    };
+
+    Some(match from {
+        "BTC" => USD_BUDGET / price_in_usdt("BTCUSDT").expect("Expected price for BTCUSDT"),
+        "ETH" => USD_BUDGET / price_in_usdt("ETHUSDT").expect("Expected price for ETHUSDT"),
+        "BNB" => USD_BUDGET / price_in_usdt("BNBUSDT").expect("Expected price for BNBUSDT"),
+        "LINK" => USD_BUDGET / price_in_usdt("LINKUSDT").expect("Expected price for LINKUSDT"),
+        "USDT" => USD_BUDGET,
+        "BUSD" => USD_BUDGET,
+        "USDC" => USD_BUDGET,
+        _ => {
+            eprintln!("{} not recognised as meaningful starting point", from);
+            return None
+        }
+    })
+}
+
+/// Prepare Cycle
+/// Resolves the starting budget, symbols, directions and live venues for a cycle. Shared by
+/// `validate_arbitrage_cycle` and `route_budget_across_cycles`; each call fetches its own
+/// depth, and the router calls it once per cycle and re-uses that snapshot across every
+/// allocation step. `pools` lets a leg be priced off a registered
+/// `ConstantProductPool` instead of an exchange order book - when a leg's symbol has no
+/// registered pool it falls back to the centralized book, so existing Bellman-Ford cycles
+/// (which only ever carry centralized pairs today) are unaffected. Order book levels cross
+/// the exchange API boundary as `f64` and are converted to `Decimal` exactly once, here.
+/// `get_orderbook_depth` returns each book alongside the epoch-seconds timestamp it was last
+/// updated at (see `OrderBookCache::get_orderbook_depth`) - that's compared against wall-clock
+/// time so a book served from a stalled cache (e.g. a local order book whose diff-depth
+/// stream silently died) can be rejected before it's traded on. Measuring how long the fetch
+/// itself took would not catch this: a stale cache read still returns in ~0ms.
+async fn prepare_cycle<T>(
+    cycle: &Vec<Edge>,
+    exchange: &T,
+    pools: &HashMap<String, ConstantProductPool>,
+) -> Result<(Decimal, Vec<String>, Vec<Direction>, Vec<Venue>), CycleRejection>
 where T: BellmanFordEx + ExchangeData + ApiCalls {
 
     // Guard: Ensure cycle
-    if cycle.len() == 0 { return None };
+    if cycle.len() == 0 { return Err(CycleRejection::EmptyCycle) };
 
     // Guard: Ensure asset holding
     let from = cycle[0].from.as_str();
     if !ASSET_HOLDINGS.contains(&from) {
         // eprintln!("Asset not in holding: {}", from);
-        return None
+        return Err(CycleRejection::UnrecognisedStart(from.to_string()));
     }
 
     // Get starting budget
-    let budget = match from {
-        "BTC" => USD_BUDGET / exchange.prices().get("BTCUSDT").expect("Expected price for BTCUSDT").to_owned(),
-        "ETH" => USD_BUDGET / exchange.prices().get("ETHUSDT").expect("Expected price for ETHUSDT").to_owned(),
-        "BNB" => USD_BUDGET / exchange.prices().get("BNBUSDT").expect("Expected price for BNBUSDT").to_owned(),
-        "LINK" => USD_BUDGET / exchange.prices().get("LINKUSDT").expect("Expected price for LINKUSDT").to_owned(),
-        "USDT" => USD_BUDGET,
-        "BUSD" => USD_BUDGET,
-        "USDC" => USD_BUDGET,
-        _ => {
-            eprintln!("{} not recognised as meaningful starting point", from);
-            return None
-        }
-    };
+    let budget = starting_budget(from, exchange).ok_or_else(|| CycleRejection::UnrecognisedStart(from.to_string()))?;
 
     // Initialize
     let mut symbols: Vec<String> = vec![];
     let mut directions: Vec<Direction> = vec![];
     let mut book_types: Vec<BookType> = vec![];
-    let mut orderbooks: Vec<Vec<(f64, f64)>> = vec![];
+    let mut pool_legs: Vec<Option<ConstantProductPool>> = vec![];
 
     // Extract info for parallel async orderbook fetching
     for leg in cycle {
@@ -178,40 +271,222 @@ where T: BellmanFordEx + ExchangeData + ApiCalls {
         let book_type = if symbol.starts_with(leg.from.as_str()) { BookType::Asks } else { BookType::Bids };
         let direction = if symbol.starts_with(leg.from.as_str()) { Direction::Forward } else { Direction::Reverse };
 
+        pool_legs.push(pools.get(&symbol).copied());
         symbols.push(symbol);
         directions.push(direction);
         book_types.push(book_type);
     }
 
-    // Build futures for orderbook asyncronous extraction
-    let futures: Vec<_> = symbols.iter().zip(book_types.iter())
-        .map(|(symbol, book_type)| exchange.get_orderbook_depth(symbol.as_str(), book_type.clone()))
+    // Build futures only for legs with no registered pool - a pool leg is priced straight off
+    // its own reserves and never needs an order-book fetch
+    let futures: Vec<_> = symbols.iter().zip(book_types.iter()).zip(pool_legs.iter())
+        .filter(|(_, pool)| pool.is_none())
+        .map(|((symbol, book_type), _)| exchange.get_orderbook_depth(symbol.as_str(), book_type.clone()))
         .collect();
 
     // Call api for orderbooks
-    let results: Vec<Result<Vec<(f64, f64)>, SmartError>> = join_all(futures).await;
+    let results: Vec<Result<(Vec<(f64, f64)>, u64), SmartError>> = join_all(futures).await;
+    let now_epoch = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+
+    // Guard: Ensure orderbook results, interleaving them back with the pool-backed legs
+    let mut fetched = results.into_iter();
+    let mut venues: Vec<Venue> = vec![];
+    for (idx, pool) in pool_legs.into_iter().enumerate() {
+        if let Some(pool) = pool {
+            venues.push(Venue::Pool(pool));
+            continue;
+        }
 
-    // Guard: Ensure orderbook results
-    for result in results {
+        let result = fetched.next().expect("one fetch result per non-pool leg");
         match result {
-            Ok(book) => orderbooks.push(book),
+            Ok((book, last_updated_at)) => {
+                // Guard: Reject if this leg's book data is already past its freshness window,
+                // judged by the book's own last-update time, not how long the call took
+                let age_secs = now_epoch.saturating_sub(last_updated_at);
+                if age_secs > MAX_BOOK_AGE_SECONDS {
+                    return Err(CycleRejection::StaleBook { symbol: symbols[idx].clone(), age_secs });
+                }
+
+                let book_decimal = book.into_iter()
+                    .filter_map(|(price, quantity)| Some((Decimal::from_f64(price)?, Decimal::from_f64(quantity)?)))
+                    .collect();
+                venues.push(Venue::OrderBook(book_decimal));
+            },
             Err(e) => {
                 eprintln!("Error fetching order book: {:?}", e);
-                return None
+                return Err(CycleRejection::OrderbookFetchFailed(e));
             },
         }
     }
 
+    Ok((budget, symbols, directions, venues))
+}
+
+/// Validate Arbitrage Cycle Verbose
+/// Validates a cycle has enough depth, isn't trading on a stale book, and isn't only
+/// profitable because a leg's execution price falls outside its own market - returning the
+/// rejection reason so callers like `run_backtest` can log why a cycle was dropped.
+/// `pools` registers any AMM pools a leg's symbol should be priced against instead of the
+/// exchange's centralized book; pass an empty map to price every leg off the order book.
+pub async fn validate_arbitrage_cycle_verbose<T: BellmanFordEx>(
+    cycle: &Vec<Edge>,
+    exchange: &T,
+    pools: &HashMap<String, ConstantProductPool>,
+) -> Result<(Decimal, Vec<f64>, Vec<String>), CycleRejection>
+where T: BellmanFordEx + ExchangeData + ApiCalls {
+
+    let (budget, symbols, directions, venues) = prepare_cycle(cycle, exchange, pools).await?;
+
     // Calculate Arbitrage
-    let Some((real_rate, quantities)) = calculate_arbitrage::<T>(&orderbooks, &symbols, &directions, budget, exchange) else { return None };
+    let (real_rate, quantities) = calculate_arbitrage::<T>(&venues, &symbols, &directions, budget, exchange)?;
 
     // Return result
-    Some((real_rate, quantities, symbols))
+    Ok((real_rate, quantities, symbols))
+}
+
+/// Validate Arbitrage Cycle
+/// Validates arbitrage cycle has enough depth
+pub async fn validate_arbitrage_cycle<T: BellmanFordEx>(
+    cycle: &Vec<Edge>,
+    exchange: &T,
+    pools: &HashMap<String, ConstantProductPool>,
+) -> Option<(Decimal, Vec<f64>, Vec<String>)>
+where T: BellmanFordEx + ExchangeData + ApiCalls {
+    validate_arbitrage_cycle_verbose(cycle, exchange, pools).await.ok()
+}
+
+/// Cycle Allocation
+/// A single cycle's share of a shared capital pool, plus the rate realised at that size
+pub struct CycleAllocation {
+    pub cycle: Vec<Edge>,
+    pub symbols: Vec<String>,
+    pub budget: Decimal,
+    pub real_rate: Decimal,
+}
+
+/// Route Budget Across Cycles
+/// Hybrid-router: instead of walking a single cycle with the whole `USD_BUDGET`, water-fills
+/// a shared pool across several profitable cycles so no single thin book collapses the
+/// realised rate. For each cycle, the marginal return of its *next* `step` of capital is
+/// re-sampled via `calculate_arbitrage` - the profit at `allocated + step` minus the profit
+/// already locked in at `allocated`, rather than the cycle's blended average rate at
+/// `allocated + step` (which decays slower than the true marginal and would keep feeding a
+/// cycle past the point its next increment is actually unprofitable). Each step of capital is
+/// greedily pushed into whichever eligible cycle currently has the highest marginal return,
+/// where "eligible" is judged against that cycle's own `arb_thresh_for_asset` gate - a
+/// stable-quoted cycle and a BTC-quoted cycle don't clear the same bar, so routing can't use
+/// one global `MIN_ARB_THRESH` floor without gating them inconsistently with the rest of the
+/// series. Returns the per-cycle allocation vector plus the blended expected rate across all
+/// capital put to work. `pools` registers any AMM pools a leg should be priced against
+/// instead of the exchange's centralized book; pass an empty map to route purely across
+/// centralized-book cycles.
+pub async fn route_budget_across_cycles<T>(
+    cycles: &Vec<Vec<Edge>>,
+    exchange: &T,
+    total_budget: Decimal,
+    pools: &HashMap<String, ConstantProductPool>,
+) -> (Vec<CycleAllocation>, Decimal)
+where T: BellmanFordEx + ExchangeData + ApiCalls {
+
+    // Gather each cycle's live depth once; every allocation step re-uses this snapshot
+    struct Candidate {
+        cycle: Vec<Edge>,
+        symbols: Vec<String>,
+        directions: Vec<Direction>,
+        venues: Vec<Venue>,
+        allocated: Decimal,
+        profit_so_far: Decimal,
+        thresh: Decimal,
+        // Units of the cycle's starting asset per USD of the shared pool
+        scale: Decimal,
+    }
+
+    let mut candidates: Vec<Candidate> = vec![];
+    for cycle in cycles {
+        match prepare_cycle(cycle, exchange, pools).await {
+            Ok((start_budget, symbols, directions, venues)) => {
+                let thresh = arb_thresh_for_asset(cycle[0].from.as_str());
+                let scale = start_budget / USD_BUDGET;
+                candidates.push(Candidate { cycle: cycle.clone(), symbols, directions, venues, allocated: Decimal::ZERO, profit_so_far: Decimal::ZERO, thresh, scale });
+            }
+            Err(reason) => eprintln!("Router: cycle dropped: {}", reason),
+        }
+    }
+
+    if candidates.is_empty() {
+        return (vec![], Decimal::ZERO);
+    }
+
+    let step = total_budget / Decimal::from(ROUTER_BUDGET_STEPS);
+    let mut remaining = total_budget;
+
+    while remaining > Decimal::ZERO {
+        // Marginal return of pushing one more `step` of capital into each candidate: the
+        // profit earned by just that next step, expressed as a rate so it's comparable to
+        // the candidate's own per-asset threshold
+        let mut best_idx: Option<usize> = None;
+        let mut best_marginal = Decimal::ZERO;
+        let mut best_trial_profit = Decimal::ZERO;
+
+        for (idx, candidate) in candidates.iter().enumerate() {
+            let trial_budget = candidate.allocated + step;
+            let (trial_rate, _) = match calculate_arbitrage::<T>(&candidate.venues, &candidate.symbols, &candidate.directions, trial_budget * candidate.scale, exchange) {
+                Ok(res) => res,
+                Err(reason) => {
+                    // Log a cycle's rejection once, on the first pass, not on every step
+                    if remaining == total_budget { eprintln!("Router: cycle dropped: {}", reason); }
+                    continue;
+                }
+            };
+
+            let trial_profit = trial_budget * (trial_rate - Decimal::ONE);
+            let marginal_rate = Decimal::ONE + (trial_profit - candidate.profit_so_far) / step;
+
+            // Eligible only if this candidate's own next increment clears its per-asset gate
+            if marginal_rate > candidate.thresh && marginal_rate > best_marginal {
+                best_marginal = marginal_rate;
+                best_idx = Some(idx);
+                best_trial_profit = trial_profit;
+            }
+        }
+
+        // Stop once no candidate's next increment clears the threshold on its own
+        let Some(idx) = best_idx else { break };
+        candidates[idx].allocated += step;
+        candidates[idx].profit_so_far = best_trial_profit;
+        remaining -= step;
+    }
+
+    // Blend the realised rate across whatever capital was actually put to work
+    let mut allocations = vec![];
+    let mut weighted_rate_sum = Decimal::ZERO;
+    let mut allocated_total = Decimal::ZERO;
+
+    for candidate in candidates {
+        if candidate.allocated <= Decimal::ZERO { continue };
+
+        let Ok((real_rate, _)) = calculate_arbitrage::<T>(&candidate.venues, &candidate.symbols, &candidate.directions, candidate.allocated * candidate.scale, exchange) else { continue };
+
+        weighted_rate_sum += real_rate * candidate.allocated;
+        allocated_total += candidate.allocated;
+
+        allocations.push(CycleAllocation {
+            cycle: candidate.cycle,
+            symbols: candidate.symbols,
+            budget: candidate.allocated,
+            real_rate,
+        });
+    }
+
+    let blended_rate = if allocated_total > Decimal::ZERO { weighted_rate_sum / allocated_total } else { Decimal::ONE };
+
+    (allocations, blended_rate)
 }
 
 /// Store Arb
-/// Stores Arb found in table for later analysis
-pub fn store_arb_cycle(cycle: &Vec<Edge>, arb_rate: f64, arb_surface: f64) -> Result<(), SmartError> {
+/// Stores Arb found in table for later analysis. `arb_rate` is the exact `Decimal` rate
+/// the cycle was realised at; it only drops to `f64` here at the CSV-serialization boundary.
+pub fn store_arb_cycle(cycle: &Vec<Edge>, arb_rate: Decimal, arb_surface: f64) -> Result<(), SmartError> {
 
     // Get unique assets
     let mut assets_hs: HashSet<String> = HashSet::new();
@@ -237,7 +512,7 @@ pub fn store_arb_cycle(cycle: &Vec<Edge>, arb_rate: f64, arb_surface: f64) -> Re
     let data: ArbData = ArbData {
         timestamp,
         arb_length,
-        arb_rate,
+        arb_rate: arb_rate.to_f64().unwrap_or(0.0),
         arb_surface,
         asset_0,
         asset_1,
@@ -267,9 +542,28 @@ pub fn store_arb_cycle(cycle: &Vec<Edge>, arb_rate: f64, arb_surface: f64) -> Re
 }
 
 /// Calculate Arbitrage Surface Rate
-/// Calculates the surface rate of an arbitrage opportunity
-fn calculate_arbitrage_surface_rate(cycle: &Vec<Edge>) -> f64 {
-    cycle.iter().fold(1.0, |acc, edge| acc * f64::exp(-edge.weight)) - 1.0
+/// Calculates the surface rate of an arbitrage opportunity. Stays `f64`: `exp` is
+/// transcendental and has no exact `Decimal` form, so this remains a fast heuristic
+/// pre-filter rather than the exact trade math in `calculate_arbitrage`. Each leg's weight
+/// is clamped to `SURFACE_RATE_WEIGHT_BOUND` before exponentiating, and the whole cycle is
+/// rejected outright if any leg's weight isn't even finite - a corrupt edge (e.g. from a
+/// zero/negative price log) would otherwise overflow `exp` to `inf` and silently poison
+/// downstream comparisons and stored `ArbData`. `pub(crate)` so `backtest::run_backtest` can
+/// pair it against a replayed `real_rate` in a `SlippageReport`.
+pub(crate) fn calculate_arbitrage_surface_rate(cycle: &Vec<Edge>) -> Option<f64> {
+    let mut acc = 1.0;
+
+    for edge in cycle {
+        if !edge.weight.is_finite() {
+            eprintln!("Rejecting cycle: leg weight {} is not finite", edge.weight);
+            return None;
+        }
+
+        let clamped_weight = edge.weight.clamp(-SURFACE_RATE_WEIGHT_BOUND, SURFACE_RATE_WEIGHT_BOUND);
+        acc *= f64::exp(-clamped_weight);
+    }
+
+    Some(acc - 1.0)
 }
 
 /// Best Symbols
@@ -290,20 +584,25 @@ pub async fn best_symbols_thread(best_symbols: Arc<Mutex<Vec<String>>>) -> Resul
 
         let exch_binance = Binance::new().await;
         let cycles = exch_binance.run_bellman_ford_multi();
-        for cycle in cycles {
-            let arb_opt = validate_arbitrage_cycle(&cycle, &exch_binance).await;
-            if let Some((arb_rate, _, _)) = arb_opt {
-
-                // // Use if wanting to store and track arbitrage opportunities
-                // let _arb_surface = calculate_arbitrage_surface_rate(&cycle) + 1.0;
-                // let _: () = arbitrage::store_arb_cycle(&cycle, arb_rate, arb_surface).unwrap();
-
-                if arb_rate >= MIN_ARB_THRESH {
-                    for leg in cycle {
-                        if symbols_hs.len() < MAX_SYMBOLS_WATCH && !ignore_list.contains(&leg.from.as_str()) { symbols_hs.insert(leg.from); }
-                        if symbols_hs.len() < MAX_SYMBOLS_WATCH && !ignore_list.contains(&leg.to.as_str()) { symbols_hs.insert(leg.to); }
-                    }
-                }
+        // No AMM pools are registered for this scan - every leg prices off the exchange's
+        // centralized order book
+        let pools: HashMap<String, ConstantProductPool> = HashMap::new();
+
+        // Route the shared USD_BUDGET across whatever cycles clear their own per-asset
+        // threshold, rather than validating (and potentially watching symbols for) every
+        // qualifying cycle as if it alone got the whole budget
+        let (allocations, _blended_rate) = route_budget_across_cycles(&cycles, &exch_binance, USD_BUDGET, &pools).await;
+
+        // // Use if wanting to store and track arbitrage opportunities
+        // for allocation in &allocations {
+        //     let Some(_arb_surface) = calculate_arbitrage_surface_rate(&allocation.cycle) else { continue };
+        //     let _: () = arbitrage::store_arb_cycle(&allocation.cycle, allocation.real_rate, _arb_surface + 1.0).unwrap();
+        // }
+
+        for allocation in allocations {
+            for leg in allocation.cycle {
+                if symbols_hs.len() < MAX_SYMBOLS_WATCH && !ignore_list.contains(&leg.from.as_str()) { symbols_hs.insert(leg.from); }
+                if symbols_hs.len() < MAX_SYMBOLS_WATCH && !ignore_list.contains(&leg.to.as_str()) { symbols_hs.insert(leg.to); }
             }
         }
 
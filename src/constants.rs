@@ -1,11 +1,42 @@
 use super::models::Mode;
 
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
 /// Searcher: Trades entire pool of assets
 /// Listener: Listens to and trades specific pool of assets
+/// Backtest: not yet a real `Mode` variant - `models::Mode` still only defines `Searcher`/
+/// `Listener`. `backtest::ReplaySource` now implements `ExchangeData`/`ApiCalls`/`BellmanFordEx`
+/// and `backtest::run_backtest` drives it over a recording end to end, so once a
+/// `Backtest(PathBuf)` (or similar) variant is added to `models::Mode`, wiring it up is just
+/// pointing the startup path at `run_backtest` instead of a live Binance run.
 pub const MODE: Mode = Mode::Searcher(true, false); // bool = is save results, bool = is trade
 
 pub const ASSET_HOLDINGS: [&str; 12] = ["USDT", "BTC","FUSD","BUSD","FUSD","BNB","AVAX","LTC","XRP","DOT","DOGE","FET"];
 pub const FIAT_EXCLUSION: [&str; 13] = ["ARS", "BIDR", "BRL", "EUR", "GBP", "IDRT", "NGN", "PLN", "RON", "RUB", "TRY", "UAH", "ZAR"];
-pub const USD_BUDGET: f64 = 1000.00; // USD equivalent in each asset holding
+pub const USD_BUDGET: Decimal = dec!(1000.00); // USD equivalent in each asset holding
 pub const MAX_CYCLE_LENGTH: usize = 5;
-pub const MIN_ARB_THRESH: f64 = 1.015; // i.e. 1.015 for 1.5%
+pub const MIN_ARB_THRESH: Decimal = dec!(1.015); // i.e. 1.015 for 1.5%, fallback for assets not listed in ARB_THRESH_BY_ASSET
+pub const ROUTER_BUDGET_STEPS: u32 = 20; // granularity of the water-filling budget router
+pub const SURFACE_RATE_WEIGHT_BOUND: f64 = 50.0; // clamp band for calculate_arbitrage_surface_rate's edge weights before exponentiating
+pub const MAX_BOOK_AGE_SECONDS: u64 = 2; // reject a cycle leg if its orderbook snapshot is older than this
+pub const MARKET_PRICE_TOLERANCE: Decimal = dec!(0.05); // max fractional deviation of a leg's implied execution price from its top of book
+
+/// Arb Threshold By Asset
+/// Per-starting-asset profit gate: stable-quoted cycles need more edge to clear spreads
+/// and fees than BTC/ETH/BNB-quoted cycles, which trade tighter books.
+pub const ARB_THRESH_BY_ASSET: [(&str, Decimal); 7] = [
+    ("USDT", dec!(1.015)),
+    ("BUSD", dec!(1.015)),
+    ("USDC", dec!(1.015)),
+    ("BTC", dec!(1.008)),
+    ("ETH", dec!(1.010)),
+    ("BNB", dec!(1.010)),
+    ("LINK", dec!(1.012)),
+];
+
+/// Arb Thresh For Asset
+/// Looks up the configured gate for a cycle's starting asset, falling back to `MIN_ARB_THRESH`
+pub fn arb_thresh_for_asset(asset: &str) -> Decimal {
+    ARB_THRESH_BY_ASSET.iter().find(|(a, _)| *a == asset).map(|(_, thresh)| *thresh).unwrap_or(MIN_ARB_THRESH)
+}
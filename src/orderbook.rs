@@ -0,0 +1,216 @@
+use super::models::{BookType, SmartError};
+
+use futures_util::StreamExt;
+use ordered_float::OrderedFloat;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+
+const DEPTH_STREAM_BASE: &str = "wss://stream.binance.com:9443/ws";
+const DEPTH_SNAPSHOT_URL: &str = "https://api.binance.com/api/v3/depth";
+const DEPTH_SNAPSHOT_LIMIT: u32 = 1000;
+
+/// Depth Snapshot
+/// REST `/api/v3/depth` response used to seed a local book
+#[derive(Debug, Deserialize)]
+struct DepthSnapshot {
+    #[serde(rename = "lastUpdateId")]
+    last_update_id: u64,
+    bids: Vec<(String, String)>,
+    asks: Vec<(String, String)>,
+}
+
+/// Depth Event
+/// Raw diff-depth payload from Binance's `<symbol>@depth` stream
+#[derive(Debug, Deserialize)]
+struct DepthEvent {
+    #[serde(rename = "U")]
+    first_update_id: u64,
+    #[serde(rename = "u")]
+    final_update_id: u64,
+    #[serde(rename = "b")]
+    bids: Vec<(String, String)>,
+    #[serde(rename = "a")]
+    asks: Vec<(String, String)>,
+}
+
+/// Local Order Book
+/// In-memory bid/ask book for a single symbol, kept in sync with Binance's diff-depth
+/// stream per https://binance-docs.github.io/apidocs/spot/en/#how-to-manage-a-local-order-book-correctly
+struct LocalOrderBook {
+    bids: BTreeMap<OrderedFloat<f64>, f64>,
+    asks: BTreeMap<OrderedFloat<f64>, f64>,
+    last_update_id: u64,
+    last_updated_at: u64,
+    synced: bool,
+}
+
+fn now_epoch_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+impl LocalOrderBook {
+    fn empty() -> Self {
+        Self { bids: BTreeMap::new(), asks: BTreeMap::new(), last_update_id: 0, last_updated_at: 0, synced: false }
+    }
+
+    /// Seed: replace the book with a REST snapshot and its `lastUpdateId`
+    fn seed(&mut self, snapshot: DepthSnapshot) {
+        self.bids.clear();
+        self.asks.clear();
+        for (price, qty) in snapshot.bids {
+            apply_level(&mut self.bids, parse(&price), parse(&qty));
+        }
+        for (price, qty) in snapshot.asks {
+            apply_level(&mut self.asks, parse(&price), parse(&qty));
+        }
+        self.last_update_id = snapshot.last_update_id;
+        self.last_updated_at = now_epoch_secs();
+        self.synced = true;
+    }
+
+    /// Apply Event
+    /// Applies a single diff-depth event, dropping stale events and flagging gaps so the
+    /// caller can re-sync from a fresh snapshot
+    fn apply_event(&mut self, event: &DepthEvent) -> Result<(), SmartError> {
+        if event.final_update_id <= self.last_update_id {
+            return Ok(());
+        }
+
+        if !self.synced {
+            return Ok(());
+        }
+
+        if event.first_update_id > self.last_update_id + 1 {
+            self.synced = false;
+            return Err(SmartError::Custom("orderbook gap detected, resync required".to_string()));
+        }
+
+        for (price, qty) in &event.bids {
+            apply_level(&mut self.bids, parse(price), parse(qty));
+        }
+        for (price, qty) in &event.asks {
+            apply_level(&mut self.asks, parse(price), parse(qty));
+        }
+        self.last_update_id = event.final_update_id;
+        self.last_updated_at = now_epoch_secs();
+
+        Ok(())
+    }
+
+    /// Bids sorted best (highest) first, in the `(price, quantity)` shape
+    /// `calculate_weighted_average_price` already expects
+    fn bids(&self) -> Vec<(f64, f64)> {
+        self.bids.iter().rev().map(|(price, qty)| (price.into_inner(), *qty)).collect()
+    }
+
+    /// Asks sorted best (lowest) first
+    fn asks(&self) -> Vec<(f64, f64)> {
+        self.asks.iter().map(|(price, qty)| (price.into_inner(), *qty)).collect()
+    }
+}
+
+fn apply_level(side: &mut BTreeMap<OrderedFloat<f64>, f64>, price: f64, quantity: f64) {
+    if quantity == 0.0 {
+        side.remove(&OrderedFloat(price));
+    } else {
+        side.insert(OrderedFloat(price), quantity);
+    }
+}
+
+fn parse(raw: &str) -> f64 {
+    raw.parse().unwrap_or(0.0)
+}
+
+/// Order Book Cache
+/// Holds one continuously-synced `LocalOrderBook` per subscribed symbol, so
+/// `get_orderbook_depth` can be served from memory instead of a REST call per cycle leg
+pub struct OrderBookCache {
+    books: Arc<Mutex<HashMap<String, LocalOrderBook>>>,
+}
+
+impl OrderBookCache {
+    pub fn new() -> Self {
+        Self { books: Arc::new(Mutex::new(HashMap::new())) }
+    }
+
+    /// Subscribe
+    /// Seeds the local book for `symbol` from a REST snapshot and spawns a task that
+    /// applies the diff-depth stream to it for the lifetime of the process
+    pub async fn subscribe(&self, symbol: &str) -> Result<(), SmartError> {
+        let symbol_lower = symbol.to_lowercase();
+
+        self.books.lock().unwrap().entry(symbol.to_string()).or_insert_with(LocalOrderBook::empty);
+
+        let stream_url = format!("{}/{}@depth@100ms", DEPTH_STREAM_BASE, symbol_lower);
+        let (ws_stream, _) = connect_async(&stream_url).await.map_err(|e| SmartError::Custom(e.to_string()))?;
+        let (_, mut read) = ws_stream.split();
+
+        let snapshot_url = format!("{}?symbol={}&limit={}", DEPTH_SNAPSHOT_URL, symbol, DEPTH_SNAPSHOT_LIMIT);
+        let snapshot: DepthSnapshot = reqwest::get(&snapshot_url).await?.json().await?;
+
+        {
+            let mut books = self.books.lock().unwrap();
+            let book = books.entry(symbol.to_string()).or_insert_with(LocalOrderBook::empty);
+            book.seed(snapshot);
+        }
+
+        let books = self.books.clone();
+        let symbol_owned = symbol.to_string();
+
+        tokio::spawn(async move {
+            while let Some(message) = read.next().await {
+                let Ok(Message::Text(text)) = message else { continue };
+                let Ok(event) = serde_json::from_str::<DepthEvent>(&text) else { continue };
+
+                let resync = {
+                    let mut books = books.lock().unwrap();
+                    let book = books.entry(symbol_owned.clone()).or_insert_with(LocalOrderBook::empty);
+                    book.apply_event(&event).is_err()
+                };
+
+                // Guard: re-sync from a fresh snapshot on any detected gap
+                if resync {
+                    if let Ok(response) = reqwest::get(&snapshot_url).await {
+                        if let Ok(snapshot) = response.json::<DepthSnapshot>().await {
+                            let mut books = books.lock().unwrap();
+                            let book = books.entry(symbol_owned.clone()).or_insert_with(LocalOrderBook::empty);
+                            book.seed(snapshot);
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(())
+    }
+
+    /// Get Depth
+    /// Reads the current local book side for `symbol` plus the epoch-seconds timestamp it was
+    /// last updated at, or `None` if not yet subscribed/synced
+    pub fn get_depth(&self, symbol: &str, bids: bool) -> Option<(Vec<(f64, f64)>, u64)> {
+        let books = self.books.lock().unwrap();
+        let book = books.get(symbol)?;
+        if !book.synced {
+            return None;
+        }
+
+        let levels = if bids { book.bids() } else { book.asks() };
+        Some((levels, book.last_updated_at))
+    }
+
+    /// Get Orderbook Depth
+    /// Same name/signature as the `ExchangeData::get_orderbook_depth` trait method, but this
+    /// is not wired into `ExchangeData for Binance` yet - `exchanges/binance.rs` isn't part of
+    /// this source tree, so that impl still has to be changed by hand to delegate its live
+    /// reads here instead of issuing a REST call per cycle leg. Once it does,
+    /// `calculate_weighted_average_price` gets served from memory, and the timestamp
+    /// returned reflects the book's own last update rather than how long this call took.
+    pub async fn get_orderbook_depth(&self, symbol: &str, book_type: BookType) -> Result<(Vec<(f64, f64)>, u64), SmartError> {
+        let bids = matches!(book_type, BookType::Bids);
+        self.get_depth(symbol, bids).ok_or_else(|| SmartError::Custom(format!("{} not subscribed/synced in order book cache", symbol)))
+    }
+}